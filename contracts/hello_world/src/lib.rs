@@ -2,7 +2,7 @@
 
 // This contract implements fair payment using a claimable balance concept.
 // It allows an employer to deposit tokens for a worker and enables a customer to process the payment.
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Map, Vec};
 
 #[derive(Clone)]
 #[contracttype]
@@ -13,6 +13,23 @@ pub enum DataKey {
     Worker,
     Customer,
     TotalTips,  // Store the total tips
+    Plan,       // The flattened condition tree for the payment plan, if one is set
+    Approvals,  // Witness addresses that have called `approve`
+    Vesting,    // The vesting schedule, if one is set
+    Roles,      // Map<Address, Vec<Role>> of every address's granted roles
+    Allocation(Address), // A still-locked `distribute` allocation awaiting this recipient's claim
+}
+
+// A grantable permission. `Employer`/`Worker`/`Customer` mirror the contract's original fixed
+// trio; `Admin` can grant and revoke roles so a single deployment can serve many workers and
+// several authorized payroll admins instead of one address per role.
+#[derive(Clone, PartialEq, Eq)]
+#[contracttype]
+pub enum Role {
+    Employer,
+    Worker,
+    Customer,
+    Admin,
 }
 
 #[derive(Clone)]
@@ -22,21 +39,134 @@ pub enum TimeBoundKind {
     After,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct TimeBound {
+    pub kind: TimeBoundKind,
+    pub timestamp: u64,
+}
+
+// Weights are basis points (out of 10_000) so `shares` must sum to 10_000.
+const TOTAL_SHARE_BPS: i128 = 10_000;
+
 #[derive(Clone)]
 #[contracttype]
 pub struct ClaimableBalance {
     pub token: Address,
     pub salary_amount: i128,
-    pub time_bound_timestamp: u64, // Store only the timestamp
+    pub time_bound: TimeBound,
+    pub claimants: Vec<Address>,
+    pub shares: Vec<i128>,
+}
+
+// A node in a flattened `Condition` tree for the optional payment plan. `contracttype` enums
+// can't hold recursion directly, so `And`/`Or` reference their children by index into the same
+// `Vec<ConditionNode>` (stored under `DataKey::Plan`) instead of nesting. Every `And`/`Or`
+// child index must point strictly earlier in the vec, so the tree is provably acyclic and the
+// root is always the last node.
+#[derive(Clone)]
+#[contracttype]
+pub enum ConditionNode {
+    Timestamp(u64),
+    Signature(Address),
+    And(u32, u32),
+    Or(u32, u32),
+}
+
+// An alternative to `ClaimableBalance` that releases `total` gradually instead of all at once:
+// nothing before `cliff`, a linear ramp from `start` to `start + duration`, and the full amount
+// once `start + duration` has passed.
+#[derive(Clone)]
+#[contracttype]
+pub struct VestingSchedule {
+    pub token: Address,
+    pub worker: Address,
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+    pub total: i128,
+    pub claimed: i128,
+}
+
+// One recipient's slice of a `distribute` batch: a verbatim amount, released immediately if
+// `unlock_timestamp` has already passed or held in escrow for the recipient to claim otherwise.
+#[derive(Clone)]
+#[contracttype]
+pub struct Allocation {
+    pub recipient: Address,
+    pub amount: i128,
+    pub unlock_timestamp: u64,
+}
+
+// An `Allocation` that was still locked at `distribute` time, escrowed under
+// `DataKey::Allocation(recipient)` until `execute_payment` releases it.
+#[derive(Clone)]
+#[contracttype]
+pub struct PendingAllocation {
+    pub token: Address,
+    pub amount: i128,
+    pub unlock_timestamp: u64,
 }
 
 #[contract]
 pub struct FairPaymentContract;
 
-// Check that the provided timestamp is after the current ledger timestamp.
-fn check_time_bound(env: &Env, time_bound_timestamp: u64) -> bool {
+// Check the time bound against the current ledger timestamp, honoring its kind:
+// `Before` passes until the ledger time exceeds the bound, `After` passes once the ledger time
+// reaches it (both pass when the ledger time equals the bound).
+fn check_time_bound(env: &Env, time_bound: &TimeBound) -> bool {
     let ledger_timestamp = env.ledger().timestamp();
-    ledger_timestamp >= time_bound_timestamp // Only allow payment after time_bound
+    match time_bound.kind {
+        TimeBoundKind::Before => ledger_timestamp <= time_bound.timestamp,
+        TimeBoundKind::After => ledger_timestamp >= time_bound.timestamp,
+    }
+}
+
+// Recursively evaluate a condition node: `Timestamp` passes once the ledger time reaches it,
+// `Signature` passes once the named witness has approved, and `And`/`Or` combine their children.
+fn evaluate_condition(env: &Env, nodes: &Vec<ConditionNode>, index: u32, approvals: &Vec<Address>) -> bool {
+    match nodes.get(index).unwrap() {
+        ConditionNode::Timestamp(timestamp) => env.ledger().timestamp() >= timestamp,
+        ConditionNode::Signature(witness) => approvals.contains(&witness),
+        ConditionNode::And(left, right) => {
+            evaluate_condition(env, nodes, left, approvals) && evaluate_condition(env, nodes, right, approvals)
+        }
+        ConditionNode::Or(left, right) => {
+            evaluate_condition(env, nodes, left, approvals) || evaluate_condition(env, nodes, right, approvals)
+        }
+    }
+}
+
+// Whether `witness` appears as a `Signature` node anywhere in the plan's condition tree.
+fn plan_has_witness(nodes: &Vec<ConditionNode>, witness: &Address) -> bool {
+    nodes.iter().any(|node| matches!(node, ConditionNode::Signature(addr) if &addr == witness))
+}
+
+// Amount of `schedule.total` vested as of ledger time `t`: 0 before the cliff, the full total
+// once the schedule has run its duration, and a linear ramp in between (integer math).
+fn vested_amount(schedule: &VestingSchedule, t: u64) -> i128 {
+    if t < schedule.cliff {
+        0
+    } else if t >= schedule.start + schedule.duration {
+        schedule.total
+    } else {
+        schedule.total * i128::from(t - schedule.start) / i128::from(schedule.duration)
+    }
+}
+
+// Fetch the caller's granted roles, defaulting to empty when the roles map or the address
+// has no entry yet.
+fn roles_of(env: &Env, addr: &Address) -> Vec<Role> {
+    let roles: Map<Address, Vec<Role>> = env.storage().instance().get(&DataKey::Roles).unwrap_or(Map::new(env));
+    roles.get(addr.clone()).unwrap_or(Vec::new(env))
+}
+
+// Panics unless `addr` has been granted `role`. Replaces the old ad-hoc
+// `if from != employer { panic!() }` identity checks.
+fn require_role(env: &Env, addr: &Address, role: Role) {
+    if !roles_of(env, addr).contains(&role) {
+        panic!("address does not have the required role");
+    }
 }
 
 #[contractimpl]
@@ -47,12 +177,53 @@ impl FairPaymentContract {
         env.storage().instance().set(&DataKey::Employer, &employer);
         env.storage().instance().set(&DataKey::Worker, &worker);
         env.storage().instance().set(&DataKey::Customer, &customer);
+
+        // Bootstrap the RBAC roles map: the employer is also the initial admin so they can
+        // grant/revoke roles for additional workers and payroll admins later.
+        let mut roles: Map<Address, Vec<Role>> = Map::new(&env);
+        roles.set(employer.clone(), Vec::from_array(&env, [Role::Employer, Role::Admin]));
+        roles.set(worker.clone(), Vec::from_array(&env, [Role::Worker]));
+        roles.set(customer.clone(), Vec::from_array(&env, [Role::Customer]));
+        env.storage().instance().set(&DataKey::Roles, &roles);
+
         // Initialize total tips
         env.storage().instance().set(&DataKey::TotalTips, &0);
         // Mark contract as initialized
         env.storage().instance().set(&DataKey::Init, &());
     }
 
+    // Grant `role` to `addr`. Callable only by an existing Admin.
+    pub fn grant_role(env: Env, admin: Address, addr: Address, role: Role) {
+        admin.require_auth();
+        require_role(&env, &admin, Role::Admin);
+
+        let mut roles: Map<Address, Vec<Role>> = env.storage().instance().get(&DataKey::Roles).unwrap_or(Map::new(&env));
+        let mut addr_roles = roles.get(addr.clone()).unwrap_or(Vec::new(&env));
+        if !addr_roles.contains(&role) {
+            addr_roles.push_back(role);
+        }
+        roles.set(addr, addr_roles);
+        env.storage().instance().set(&DataKey::Roles, &roles);
+    }
+
+    // Revoke `role` from `addr`. Callable only by an existing Admin.
+    pub fn revoke_role(env: Env, admin: Address, addr: Address, role: Role) {
+        admin.require_auth();
+        require_role(&env, &admin, Role::Admin);
+
+        let mut roles: Map<Address, Vec<Role>> = env.storage().instance().get(&DataKey::Roles).unwrap_or(Map::new(&env));
+        if let Some(addr_roles) = roles.get(addr.clone()) {
+            let mut remaining = Vec::new(&env);
+            for existing in addr_roles.iter() {
+                if existing != role {
+                    remaining.push_back(existing);
+                }
+            }
+            roles.set(addr, remaining);
+            env.storage().instance().set(&DataKey::Roles, &roles);
+        }
+    }
+
     pub fn make_payments(
         env: Env,
         from: Address,
@@ -82,13 +253,74 @@ impl FairPaymentContract {
         }
     }
 
+    // One-shot payroll/airdrop run with per-recipient amounts and lockup dates. Already-unlocked
+    // allocations pay out immediately; still-locked ones are escrowed for the recipient to pull
+    // later via `execute_payment`. The whole batch is all-or-nothing.
+    pub fn distribute(env: Env, from: Address, allocations: Vec<Allocation>, token: Address) {
+        from.require_auth();
+        require_role(&env, &from, Role::Employer);
+
+        let total: i128 = allocations.iter().map(|allocation| allocation.amount).sum();
+        let token_client = token::Client::new(&env, &token);
+        if token_client.balance(&from) < total {
+            panic!("sender balance cannot cover the full distribution");
+        }
+
+        // Locked allocations are merged per recipient (amounts summed, latest unlock wins)
+        // rather than written straight to storage, so two tranches for the same recipient in
+        // one batch - or a batch landing on a recipient with an already-escrowed tranche -
+        // can't silently clobber an earlier `PendingAllocation` and strand its tokens.
+        let now = env.ledger().timestamp();
+        let mut pending: Map<Address, PendingAllocation> = Map::new(&env);
+        for allocation in allocations.iter() {
+            if allocation.unlock_timestamp <= now {
+                token_client.transfer(&from, &allocation.recipient, &allocation.amount);
+                continue;
+            }
+
+            token_client.transfer(&from, &env.current_contract_address(), &allocation.amount);
+
+            let existing = pending.get(allocation.recipient.clone()).or_else(|| {
+                env.storage()
+                    .instance()
+                    .get::<_, PendingAllocation>(&DataKey::Allocation(allocation.recipient.clone()))
+            });
+            let merged = match existing {
+                Some(prior) => {
+                    if prior.token != token {
+                        panic!("recipient already has an escrowed allocation in a different token");
+                    }
+                    PendingAllocation {
+                        token: token.clone(),
+                        amount: prior.amount + allocation.amount,
+                        unlock_timestamp: prior.unlock_timestamp.max(allocation.unlock_timestamp),
+                    }
+                }
+                None => PendingAllocation {
+                    token: token.clone(),
+                    amount: allocation.amount,
+                    unlock_timestamp: allocation.unlock_timestamp,
+                },
+            };
+            pending.set(allocation.recipient.clone(), merged);
+        }
+
+        for recipient in pending.keys().iter() {
+            let allocation = pending.get(recipient.clone()).unwrap();
+            env.storage().instance().set(&DataKey::Allocation(recipient), &allocation);
+        }
+    }
+
     // Deposit salary and set up claimable balance
     pub fn deposit_salary(
         env: Env,
         from: Address,
         token: Address,
         salary_amount: i128,
-        time_bound_timestamp: u64, // Accept timestamp directly
+        time_bound_kind: TimeBoundKind,
+        time_bound_timestamp: u64,
+        claimants: Vec<Address>,
+        shares: Vec<i128>,
     ) {
         from.require_auth();
 
@@ -96,26 +328,170 @@ impl FairPaymentContract {
             panic!("contract has not been initialized");
         }
 
-        // Ensure the sender is the employer
-        let employer: Address = env.storage().instance().get(&DataKey::Employer).unwrap();
-        if from != employer {
-            panic!("only the employer can deposit salary");
+        require_role(&env, &from, Role::Employer);
+
+        if claimants.len() != shares.len() || claimants.is_empty() {
+            panic!("claimants and shares must be the same non-empty length");
+        }
+        let total_shares: i128 = shares.iter().sum();
+        if total_shares != TOTAL_SHARE_BPS {
+            panic!("shares must sum to 10_000 basis points");
         }
 
         // Transfer token from `from` to this contract address.
         token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &salary_amount);
-        
-        // Store the salary info to allow the worker to claim it.
+
+        // Store the salary info to allow the claimants to claim it.
         env.storage().instance().set(
             &DataKey::Balance,
             &ClaimableBalance {
                 token,
                 salary_amount,
-                time_bound_timestamp, // Store the timestamp
+                time_bound: TimeBound {
+                    kind: time_bound_kind,
+                    timestamp: time_bound_timestamp,
+                },
+                claimants,
+                shares,
             },
         );
     }
 
+    // Deposit salary under a vesting schedule instead of the all-or-nothing claimable balance.
+    pub fn deposit_vesting(
+        env: Env,
+        from: Address,
+        token: Address,
+        worker: Address,
+        total: i128,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+    ) {
+        from.require_auth();
+
+        if !is_initialized(&env) {
+            panic!("contract has not been initialized");
+        }
+
+        require_role(&env, &from, Role::Employer);
+        if duration == 0 {
+            panic!("vesting duration must be greater than zero");
+        }
+        if cliff < start {
+            panic!("cliff must not be before the vesting start");
+        }
+        if start.checked_add(duration).is_none() {
+            panic!("start + duration overflows");
+        }
+
+        token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &total);
+
+        env.storage().instance().set(
+            &DataKey::Vesting,
+            &VestingSchedule {
+                token,
+                worker,
+                start,
+                cliff,
+                duration,
+                total,
+                claimed: 0,
+            },
+        );
+    }
+
+    // Pay the claimant whatever has vested since their last claim.
+    pub fn claim_vested(env: Env, claimant: Address) {
+        claimant.require_auth();
+
+        let mut schedule: VestingSchedule = env.storage().instance().get(&DataKey::Vesting).unwrap();
+        if claimant != schedule.worker {
+            panic!("only the vesting worker can claim");
+        }
+
+        let payable = vested_amount(&schedule, env.ledger().timestamp()) - schedule.claimed;
+        if payable <= 0 {
+            panic!("nothing has vested yet");
+        }
+
+        schedule.claimed += payable;
+        token::Client::new(&env, &schedule.token).transfer(
+            &env.current_contract_address(),
+            &claimant,
+            &payable,
+        );
+        env.storage().instance().set(&DataKey::Vesting, &schedule);
+    }
+
+    // Employer-only early termination: pay the worker everything vested so far and return the
+    // unvested remainder to `from`, whoever currently holds the Employer role.
+    pub fn revoke_vesting(env: Env, from: Address) {
+        from.require_auth();
+        require_role(&env, &from, Role::Employer);
+
+        let schedule: VestingSchedule = env.storage().instance().get(&DataKey::Vesting).unwrap();
+        let vested = vested_amount(&schedule, env.ledger().timestamp());
+        let payable = vested - schedule.claimed;
+        let remainder = schedule.total - vested;
+
+        let token_client = token::Client::new(&env, &schedule.token);
+        if payable > 0 {
+            token_client.transfer(&env.current_contract_address(), &schedule.worker, &payable);
+        }
+        if remainder > 0 {
+            token_client.transfer(&env.current_contract_address(), &from, &remainder);
+        }
+
+        env.storage().instance().remove(&DataKey::Vesting);
+    }
+
+    // Set (or replace) the payment plan's condition tree, gating `execute_payment` in addition
+    // to the claimable balance's time bound.
+    pub fn set_payment_plan(env: Env, from: Address, nodes: Vec<ConditionNode>) {
+        from.require_auth();
+        require_role(&env, &from, Role::Employer);
+        if nodes.is_empty() {
+            panic!("payment plan must have at least one condition node");
+        }
+        // `And`/`Or` children must point strictly earlier in the vec, so the tree is provably
+        // acyclic and `evaluate_condition` can't recurse without bound.
+        for (index, node) in nodes.iter().enumerate() {
+            let children = match node {
+                ConditionNode::And(left, right) | ConditionNode::Or(left, right) => Some((left, right)),
+                _ => None,
+            };
+            if let Some((left, right)) = children {
+                if left >= index as u32 || right >= index as u32 {
+                    panic!("And/Or child indices must point strictly earlier in the condition tree");
+                }
+            }
+        }
+
+        env.storage().instance().set(&DataKey::Plan, &nodes);
+        env.storage().instance().set(&DataKey::Approvals, &Vec::<Address>::new(&env));
+    }
+
+    // Record a witness's sign-off on a `Signature` condition in the payment plan.
+    pub fn approve(env: Env, witness: Address) {
+        witness.require_auth();
+
+        let nodes: Vec<ConditionNode> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Plan)
+            .unwrap_or_else(|| panic!("no payment plan has been set"));
+        if !plan_has_witness(&nodes, &witness) {
+            panic!("address is not a witness in the payment plan");
+        }
+
+        let mut approvals: Vec<Address> = env.storage().instance().get(&DataKey::Approvals).unwrap();
+        if !approvals.contains(&witness) {
+            approvals.push_back(witness);
+            env.storage().instance().set(&DataKey::Approvals, &approvals);
+        }
+    }
+
     // Deposit tips into the pool
     pub fn deposit_tip(env: Env, from: Address, token: Address, amount: i32) {
         if !is_initialized(&env) {
@@ -124,6 +500,7 @@ impl FairPaymentContract {
 
         let amount_i128 = i128::from(amount);
         from.require_auth();
+        require_role(&env, &from, Role::Customer);
         // Transfer token from `from` to this contract address.
         token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &amount_i128);
 
@@ -133,38 +510,82 @@ impl FairPaymentContract {
         env.storage().instance().set(&DataKey::TotalTips, &total_tips);
     }
 
-    // Execute payment to the worker by the customer
-    pub fn execute_payment(env: Env, claimant: Address, token: Address) {        // Require authorization from the caller (worker)
+    // Execute payment, splitting the balance across all authorized claimants by their share.
+    // `claimant` must be one of the authorized claimants and authorizes the call, but every
+    // claimant is paid out in the same call since the balance is settled all at once.
+    pub fn execute_payment(env: Env, claimant: Address, token: Address) {
         claimant.require_auth();
-    
+
+        // A locked `distribute` allocation for this claimant takes priority over the shared
+        // claimable balance below; it is a separate, per-recipient escrow entry.
+        if let Some(pending) = env
+            .storage()
+            .instance()
+            .get::<_, PendingAllocation>(&DataKey::Allocation(claimant.clone()))
+        {
+            if env.ledger().timestamp() < pending.unlock_timestamp {
+                panic!("allocation is still locked");
+            }
+            token::Client::new(&env, &pending.token).transfer(
+                &env.current_contract_address(),
+                &claimant,
+                &pending.amount,
+            );
+            env.storage().instance().remove(&DataKey::Allocation(claimant));
+            return;
+        }
+
         // Retrieve claimable balance
         let claimable_balance: ClaimableBalance =
             env.storage().instance().get(&DataKey::Balance).unwrap();
-    
+
         // Check the time bounds
-        if !check_time_bound(&env, claimable_balance.time_bound_timestamp) {
-            panic!("payment cannot be executed before the time bound");
+        if !check_time_bound(&env, &claimable_balance.time_bound) {
+            panic!("payment cannot be executed outside the time bound");
         }
-    
+
+        if !claimable_balance.claimants.contains(&claimant) {
+            panic!("caller is not an authorized claimant");
+        }
+        require_role(&env, &claimant, Role::Worker);
+
+        // If a payment plan is set, its condition tree must also resolve true.
+        if let Some(nodes) = env.storage().instance().get::<_, Vec<ConditionNode>>(&DataKey::Plan) {
+            let approvals: Vec<Address> = env.storage().instance().get(&DataKey::Approvals).unwrap();
+            if !evaluate_condition(&env, &nodes, nodes.len() - 1, &approvals) {
+                panic!("payment plan condition has not been satisfied");
+            }
+        }
+
         // Retrieve total tips
         let total_tips: i32 = env.storage().instance().get(&DataKey::TotalTips).unwrap();
-    
-        // Calculate the total amount to transfer to the worker
+
+        // Calculate the total amount to split among the claimants
         let total_amount = claimable_balance.salary_amount + i128::from(total_tips);
-    
-        // Transfer the total amount of tokens to the worker
-        token::Client::new(&env, &token).transfer(
-            &env.current_contract_address(),
-            &claimant,
-            &total_amount,
-        );
-    
+
+        // Split the total by each claimant's share, putting any integer-division
+        // remainder on the last claimant so the slices reconcile exactly.
+        let token_client = token::Client::new(&env, &token);
+        let last_index = claimable_balance.claimants.len() - 1;
+        let mut distributed: i128 = 0;
+        for (index, recipient) in claimable_balance.claimants.iter().enumerate() {
+            let amount = if index as u32 == last_index {
+                total_amount - distributed
+            } else {
+                let share = claimable_balance.shares.get(index as u32).unwrap();
+                let portion = (total_amount * share) / TOTAL_SHARE_BPS;
+                distributed += portion;
+                portion
+            };
+            token_client.transfer(&env.current_contract_address(), &recipient, &amount);
+        }
+
         // Remove the balance entry to prevent any further claims
         env.storage().instance().remove(&DataKey::Balance);
-        
+
         // Reset total tips after payment
         env.storage().instance().set(&DataKey::TotalTips, &0);
-    }    
+    }
 }
 
 // Check if the contract has been initialized
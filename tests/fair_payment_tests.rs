@@ -1,9 +1,11 @@
 use soroban_sdk::Env;
 use soroban_sdk::testutils::{contract::Client, Accounts};
-use soroban_sdk::Address;
+use soroban_sdk::{token, Address};
 
 // Import your contract module
-use hello_world::FairPaymentContract;
+use hello_world::{
+    Allocation, ConditionNode, FairPaymentContract, PendingAllocation, Role, TimeBoundKind, VestingSchedule,
+};
 
 #[cfg(test)]
 mod tests {
@@ -41,14 +43,24 @@ mod tests {
         let salary_amount = 1000;
         let time_bound_timestamp = env.ledger().timestamp() + 3600; // 1 hour in the future
 
-        // Employer deposits salary
-        contract.deposit_salary(&env, employer.clone(), token_address, salary_amount, time_bound_timestamp);
+        // Employer deposits salary, claimable only after the time bound
+        contract.deposit_salary(
+            &env,
+            employer.clone(),
+            token_address,
+            salary_amount,
+            TimeBoundKind::After,
+            time_bound_timestamp,
+            Vec::from_array(&env, [worker.clone()]),
+            Vec::from_array(&env, [10_000]),
+        );
 
         // Verify balance is set correctly
         let claimable_balance: ClaimableBalance = env.storage().instance().get(&DataKey::Balance).unwrap();
         assert_eq!(claimable_balance.salary_amount, salary_amount);
         assert_eq!(claimable_balance.token, token_address);
-        assert_eq!(claimable_balance.time_bound_timestamp, time_bound_timestamp);
+        assert_eq!(claimable_balance.time_bound.timestamp, time_bound_timestamp);
+        assert_eq!(claimable_balance.claimants, Vec::from_array(&env, [worker.clone()]));
     }
 
     #[test]
@@ -62,8 +74,8 @@ mod tests {
         let token_address = Address::from([0u8; 32]); // Replace with actual token address
         let tip_amount = 500;
 
-        // Employer deposits a tip
-        contract.deposit_tip(&env, employer.clone(), token_address, tip_amount);
+        // Customer deposits a tip
+        contract.deposit_tip(&env, customer.clone(), token_address, tip_amount);
 
         // Verify total tips
         let total_tips: i128 = env.storage().instance().get(&DataKey::TotalTips).unwrap();
@@ -82,18 +94,27 @@ mod tests {
         let salary_amount = 1000;
         let time_bound_timestamp = env.ledger().timestamp() + 3600; // 1 hour in the future
 
-        // Employer deposits salary
-        contract.deposit_salary(&env, employer.clone(), token_address, salary_amount, time_bound_timestamp);
-
-        // Employer deposits a tip
+        // Employer deposits salary, claimable only after the time bound
+        contract.deposit_salary(
+            &env,
+            employer.clone(),
+            token_address,
+            salary_amount,
+            TimeBoundKind::After,
+            time_bound_timestamp,
+            Vec::from_array(&env, [worker.clone()]),
+            Vec::from_array(&env, [10_000]),
+        );
+
+        // Customer deposits a tip
         let tip_amount = 500;
-        contract.deposit_tip(&env, employer.clone(), token_address, tip_amount);
+        contract.deposit_tip(&env, customer.clone(), token_address, tip_amount);
 
         // Simulate time passing
         env.ledger().advance_timestamp(3601); // Advance 1 hour
 
         // Execute payment
-        contract.execute_payment(&env);
+        contract.execute_payment(&env, worker.clone(), token_address);
 
         // Verify balance removal
         assert!(env.storage().instance().get(&DataKey::Balance).is_none());
@@ -107,7 +128,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "only the employer can deposit salary")]
+    #[should_panic(expected = "address does not have the required role")]
     fn test_only_employer_can_deposit_salary() {
         let env = Env::default();
         let (employer, worker, customer) = env.accounts().generate(3);
@@ -120,7 +141,16 @@ mod tests {
         let time_bound_timestamp = env.ledger().timestamp() + 3600;
 
         // Worker attempts to deposit salary, should panic
-        contract.deposit_salary(&env, worker.clone(), token_address, salary_amount, time_bound_timestamp);
+        contract.deposit_salary(
+            &env,
+            worker.clone(),
+            token_address,
+            salary_amount,
+            TimeBoundKind::After,
+            time_bound_timestamp,
+            Vec::from_array(&env, [worker.clone()]),
+            Vec::from_array(&env, [10_000]),
+        );
     }
 
     #[test]
@@ -134,6 +164,625 @@ mod tests {
         // Attempt to execute payment without initialization, should panic
         contract.execute_payment(&env);
     }
+
+    #[test]
+    fn test_set_payment_plan_and_approve_gate_execute_payment() {
+        let env = Env::default();
+        let (employer, worker, customer, witness) = env.accounts().generate(4);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let salary_amount = 1000;
+        let time_bound_timestamp = env.ledger().timestamp();
+
+        contract.deposit_salary(
+            &env,
+            employer.clone(),
+            token_address,
+            salary_amount,
+            TimeBoundKind::After,
+            time_bound_timestamp,
+            Vec::from_array(&env, [worker.clone()]),
+            Vec::from_array(&env, [10_000]),
+        );
+
+        // Plan: release requires both the witness's sign-off and the ledger to have passed
+        // `time_bound_timestamp`. Root (the `And`) must be the last node.
+        let nodes = Vec::from_array(
+            &env,
+            [
+                ConditionNode::Signature(witness.clone()),
+                ConditionNode::Timestamp(time_bound_timestamp),
+                ConditionNode::And(0, 1),
+            ],
+        );
+        contract.set_payment_plan(&env, employer.clone(), nodes);
+
+        // Witness signs off
+        contract.approve(&env, witness.clone());
+
+        // Both the time bound and the plan condition are satisfied now
+        contract.execute_payment(&env, worker.clone(), token_address);
+
+        assert!(env.storage().instance().get(&DataKey::Balance).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "address does not have the required role")]
+    fn test_set_payment_plan_requires_employer_role() {
+        let env = Env::default();
+        let (employer, worker, customer, witness) = env.accounts().generate(4);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let nodes = Vec::from_array(&env, [ConditionNode::Signature(witness.clone())]);
+
+        // Worker is not an Employer, should panic
+        contract.set_payment_plan(&env, worker.clone(), nodes);
+    }
+
+    #[test]
+    #[should_panic(expected = "address is not a witness in the payment plan")]
+    fn test_approve_rejects_unknown_witness() {
+        let env = Env::default();
+        let (employer, worker, customer, witness, stranger) = env.accounts().generate(5);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let nodes = Vec::from_array(&env, [ConditionNode::Signature(witness.clone())]);
+        contract.set_payment_plan(&env, employer.clone(), nodes);
+
+        // `stranger` never appears as a `Signature` node, should panic
+        contract.approve(&env, stranger.clone());
+    }
+
+    #[test]
+    fn test_deposit_vesting_and_claim_vested() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let start = env.ledger().timestamp();
+        let cliff = start + 100;
+        let duration = 1000;
+        let total = 1000;
+
+        contract.deposit_vesting(&env, employer.clone(), token_address, worker.clone(), total, start, cliff, duration);
+
+        // Halfway through the vesting window, half of `total` should be claimable
+        env.ledger().advance_timestamp(500);
+        contract.claim_vested(&env, worker.clone());
+
+        let schedule: VestingSchedule = env.storage().instance().get(&DataKey::Vesting).unwrap();
+        assert_eq!(schedule.claimed, 500);
+    }
+
+    #[test]
+    #[should_panic(expected = "cliff must not be before the vesting start")]
+    fn test_deposit_vesting_rejects_cliff_before_start() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let start = env.ledger().timestamp() + 100;
+        let cliff = start - 1;
+
+        // cliff is before start, should panic
+        contract.deposit_vesting(&env, employer.clone(), token_address, worker.clone(), 1000, start, cliff, 1000);
+    }
+
+    #[test]
+    #[should_panic(expected = "nothing has vested yet")]
+    fn test_claim_vested_before_cliff_panics() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let start = env.ledger().timestamp();
+        let cliff = start + 100;
+
+        contract.deposit_vesting(&env, employer.clone(), token_address, worker.clone(), 1000, start, cliff, 1000);
+
+        // Still before the cliff, should panic
+        contract.claim_vested(&env, worker.clone());
+    }
+
+    #[test]
+    fn test_revoke_vesting_pays_worker_and_returns_remainder() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let start = env.ledger().timestamp();
+
+        contract.deposit_vesting(&env, employer.clone(), token_address, worker.clone(), 1000, start, start, 1000);
+
+        env.ledger().advance_timestamp(500);
+        contract.revoke_vesting(&env, employer.clone());
+
+        assert!(env.storage().instance().get(&DataKey::Vesting).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "address does not have the required role")]
+    fn test_revoke_vesting_requires_employer_role() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let start = env.ledger().timestamp();
+
+        contract.deposit_vesting(&env, employer.clone(), token_address, worker.clone(), 1000, start, start, 1000);
+
+        // Worker is not an Employer, should panic
+        contract.revoke_vesting(&env, worker.clone());
+    }
+
+    #[test]
+    fn test_grant_role_enables_new_customer_to_deposit_tip() {
+        let env = Env::default();
+        let (employer, worker, customer, new_customer) = env.accounts().generate(4);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+
+        // Employer (the initial Admin) grants the Customer role to a new address
+        contract.grant_role(&env, employer.clone(), new_customer.clone(), Role::Customer);
+
+        // The newly granted address can now deposit tips
+        contract.deposit_tip(&env, new_customer.clone(), token_address, 250);
+
+        let total_tips: i128 = env.storage().instance().get(&DataKey::TotalTips).unwrap();
+        assert_eq!(total_tips, 250);
+    }
+
+    #[test]
+    #[should_panic(expected = "address does not have the required role")]
+    fn test_grant_role_requires_admin_role() {
+        let env = Env::default();
+        let (employer, worker, customer, new_customer) = env.accounts().generate(4);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        // Worker is not an Admin, should panic
+        contract.grant_role(&env, worker.clone(), new_customer.clone(), Role::Customer);
+    }
+
+    #[test]
+    #[should_panic(expected = "address does not have the required role")]
+    fn test_revoke_role_blocks_future_calls() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+
+        // Employer revokes the Customer's Customer role
+        contract.revoke_role(&env, employer.clone(), customer.clone(), Role::Customer);
+
+        // The customer can no longer deposit tips, should panic
+        contract.deposit_tip(&env, customer.clone(), token_address, 250);
+    }
+
+    #[test]
+    #[should_panic(expected = "address does not have the required role")]
+    fn test_revoke_role_requires_admin_role() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        // Worker is not an Admin, should panic
+        contract.revoke_role(&env, worker.clone(), customer.clone(), Role::Customer);
+    }
+
+    #[test]
+    fn test_distribute_escrows_locked_allocations() {
+        let env = Env::default();
+        let (employer, worker, customer, other_worker) = env.accounts().generate(4);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+        contract.grant_role(&env, employer.clone(), other_worker.clone(), Role::Worker);
+
+        let token_address = Address::from([0u8; 32]);
+        let now = env.ledger().timestamp();
+
+        let allocations = Vec::from_array(
+            &env,
+            [
+                // Already unlocked: pays out immediately, nothing escrowed.
+                Allocation { recipient: worker.clone(), amount: 100, unlock_timestamp: now },
+                // Still locked: escrowed for a later `execute_payment` pull.
+                Allocation { recipient: other_worker.clone(), amount: 200, unlock_timestamp: now + 1000 },
+            ],
+        );
+        contract.distribute(&env, employer.clone(), allocations, token_address);
+
+        assert!(env.storage().instance().get(&DataKey::Allocation(worker.clone())).is_none());
+        let pending: PendingAllocation = env
+            .storage()
+            .instance()
+            .get(&DataKey::Allocation(other_worker.clone()))
+            .unwrap();
+        assert_eq!(pending.amount, 200);
+    }
+
+    #[test]
+    fn test_distribute_merges_duplicate_recipient_locked_allocations() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let now = env.ledger().timestamp();
+
+        // Two locked tranches for the same recipient in one batch.
+        let allocations = Vec::from_array(
+            &env,
+            [
+                Allocation { recipient: worker.clone(), amount: 100, unlock_timestamp: now + 500 },
+                Allocation { recipient: worker.clone(), amount: 50, unlock_timestamp: now + 1000 },
+            ],
+        );
+        contract.distribute(&env, employer.clone(), allocations, token_address);
+
+        // Both tranches' tokens must still be accounted for in a single merged entry.
+        let pending: PendingAllocation = env
+            .storage()
+            .instance()
+            .get(&DataKey::Allocation(worker.clone()))
+            .unwrap();
+        assert_eq!(pending.amount, 150);
+        assert_eq!(pending.unlock_timestamp, now + 1000);
+    }
+
+    #[test]
+    #[should_panic(expected = "address does not have the required role")]
+    fn test_distribute_requires_employer_role() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let allocations = Vec::from_array(
+            &env,
+            [Allocation { recipient: worker.clone(), amount: 100, unlock_timestamp: env.ledger().timestamp() }],
+        );
+
+        // Worker is not an Employer, should panic
+        contract.distribute(&env, worker.clone(), allocations, token_address);
+    }
+
+    // Deploys a real Stellar Asset Contract so payouts can be checked by token balance instead
+    // of only by storage side effects, unlike the fake zero-address token used elsewhere.
+    fn create_token(env: &Env, admin: &Address) -> (Address, token::Client<'static>) {
+        let token_address = env.register_stellar_asset_contract_v2(admin.clone()).address();
+        (token_address.clone(), token::Client::new(env, &token_address))
+    }
+
+    #[test]
+    fn test_execute_payment_splits_across_weighted_claimants_with_remainder() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (employer, worker, customer, worker2) = env.accounts().generate(4);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+        contract.grant_role(&env, employer.clone(), worker2.clone(), Role::Worker);
+
+        let (token_address, token_client) = create_token(&env, &employer);
+        token::StellarAssetClient::new(&env, &token_address).mint(&employer, &10_000);
+        token::StellarAssetClient::new(&env, &token_address).mint(&customer, &10_000);
+
+        let salary_amount = 1000;
+        let time_bound_timestamp = env.ledger().timestamp();
+
+        contract.deposit_salary(
+            &env,
+            employer.clone(),
+            token_address.clone(),
+            salary_amount,
+            TimeBoundKind::After,
+            time_bound_timestamp,
+            Vec::from_array(&env, [worker.clone(), worker2.clone()]),
+            Vec::from_array(&env, [3_333, 6_667]),
+        );
+        contract.deposit_tip(&env, customer.clone(), token_address.clone(), 500);
+
+        contract.execute_payment(&env, worker.clone(), token_address.clone());
+
+        // total_amount = 1000 + 500 = 1500; worker's 33.33% share floors to 499 and the
+        // remainder (including the rounding lost to integer division) lands on worker2.
+        assert_eq!(token_client.balance(&worker), 499);
+        assert_eq!(token_client.balance(&worker2), 1001);
+    }
+
+    #[test]
+    #[should_panic(expected = "caller is not an authorized claimant")]
+    fn test_execute_payment_rejects_non_claimant() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (employer, worker, customer, worker2) = env.accounts().generate(4);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+        // worker2 has a legitimate Worker role, just not on this particular balance.
+        contract.grant_role(&env, employer.clone(), worker2.clone(), Role::Worker);
+
+        let (token_address, _token_client) = create_token(&env, &employer);
+        token::StellarAssetClient::new(&env, &token_address).mint(&employer, &10_000);
+
+        let salary_amount = 1000;
+        let time_bound_timestamp = env.ledger().timestamp();
+        contract.deposit_salary(
+            &env,
+            employer.clone(),
+            token_address.clone(),
+            salary_amount,
+            TimeBoundKind::After,
+            time_bound_timestamp,
+            Vec::from_array(&env, [worker.clone()]),
+            Vec::from_array(&env, [10_000]),
+        );
+
+        // worker2 is not in the claimants list, should panic before the role check is reached.
+        contract.execute_payment(&env, worker2.clone(), token_address);
+    }
+
+    #[test]
+    fn test_execute_payment_before_time_bound_succeeds_before_deadline() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let salary_amount = 1000;
+        // A bonus that lapses: only claimable up to this deadline.
+        let time_bound_timestamp = env.ledger().timestamp() + 3600;
+
+        contract.deposit_salary(
+            &env,
+            employer.clone(),
+            token_address,
+            salary_amount,
+            TimeBoundKind::Before,
+            time_bound_timestamp,
+            Vec::from_array(&env, [worker.clone()]),
+            Vec::from_array(&env, [10_000]),
+        );
+
+        // Still before the deadline, should succeed.
+        contract.execute_payment(&env, worker.clone(), token_address);
+
+        assert!(env.storage().instance().get(&DataKey::Balance).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "payment cannot be executed outside the time bound")]
+    fn test_execute_payment_before_time_bound_panics_after_deadline() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let salary_amount = 1000;
+        let time_bound_timestamp = env.ledger().timestamp() + 3600;
+
+        contract.deposit_salary(
+            &env,
+            employer.clone(),
+            token_address,
+            salary_amount,
+            TimeBoundKind::Before,
+            time_bound_timestamp,
+            Vec::from_array(&env, [worker.clone()]),
+            Vec::from_array(&env, [10_000]),
+        );
+
+        // Past the deadline - the bonus has lapsed, should panic.
+        env.ledger().advance_timestamp(3601);
+        contract.execute_payment(&env, worker.clone(), token_address);
+    }
+
+    #[test]
+    #[should_panic(expected = "And/Or child indices must point strictly earlier in the condition tree")]
+    fn test_set_payment_plan_rejects_self_referencing_index() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        // Node 0 references itself instead of an earlier node, should panic.
+        let nodes = Vec::from_array(&env, [ConditionNode::And(0, 0)]);
+        contract.set_payment_plan(&env, employer.clone(), nodes);
+    }
+
+    #[test]
+    fn test_or_condition_resolves_true_via_either_branch() {
+        let env = Env::default();
+        let (employer, worker, customer, witness) = env.accounts().generate(4);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let salary_amount = 1000;
+        let time_bound_timestamp = env.ledger().timestamp();
+
+        contract.deposit_salary(
+            &env,
+            employer.clone(),
+            token_address,
+            salary_amount,
+            TimeBoundKind::After,
+            time_bound_timestamp,
+            Vec::from_array(&env, [worker.clone()]),
+            Vec::from_array(&env, [10_000]),
+        );
+
+        // Plan releases once EITHER the witness signs OR the already-past timestamp is reached.
+        // The witness never signs - only the Timestamp branch is true.
+        let nodes = Vec::from_array(
+            &env,
+            [
+                ConditionNode::Signature(witness.clone()),
+                ConditionNode::Timestamp(time_bound_timestamp),
+                ConditionNode::Or(0, 1),
+            ],
+        );
+        contract.set_payment_plan(&env, employer.clone(), nodes);
+
+        // No approval needed - the Timestamp branch alone satisfies the Or.
+        contract.execute_payment(&env, worker.clone(), token_address);
+
+        assert!(env.storage().instance().get(&DataKey::Balance).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "start + duration overflows")]
+    fn test_deposit_vesting_rejects_overflowing_window() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let start = u64::MAX;
+
+        // start + duration overflows u64, should panic.
+        contract.deposit_vesting(&env, employer.clone(), token_address, worker.clone(), 1000, start, start, 1);
+    }
+
+    #[test]
+    fn test_claim_vested_after_duration_pays_full_total() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let start = env.ledger().timestamp();
+        let duration = 1000;
+
+        contract.deposit_vesting(&env, employer.clone(), token_address, worker.clone(), 1000, start, start, duration);
+
+        // Past start + duration, the full total should be claimable in one call.
+        env.ledger().advance_timestamp(duration + 1);
+        contract.claim_vested(&env, worker.clone());
+
+        let schedule: VestingSchedule = env.storage().instance().get(&DataKey::Vesting).unwrap();
+        assert_eq!(schedule.claimed, 1000);
+    }
+
+    #[test]
+    #[should_panic(expected = "nothing has vested yet")]
+    fn test_claim_vested_twice_does_not_double_pay() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let start = env.ledger().timestamp();
+
+        contract.deposit_vesting(&env, employer.clone(), token_address, worker.clone(), 1000, start, start, 1000);
+
+        env.ledger().advance_timestamp(500);
+        contract.claim_vested(&env, worker.clone());
+
+        // No further time has passed since the first claim, so nothing new has vested -
+        // this must panic rather than paying out (and incrementing `claimed`) a second time.
+        contract.claim_vested(&env, worker.clone());
+    }
+
+    #[test]
+    #[should_panic(expected = "sender balance cannot cover the full distribution")]
+    fn test_distribute_rejects_insufficient_balance() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let (token_address, _token_client) = create_token(&env, &employer);
+        // Employer only has 50 tokens, but the batch below totals 100.
+        token::StellarAssetClient::new(&env, &token_address).mint(&employer, &50);
+
+        let allocations = Vec::from_array(
+            &env,
+            [Allocation { recipient: worker.clone(), amount: 100, unlock_timestamp: env.ledger().timestamp() }],
+        );
+
+        // Should panic on the balance precheck before any transfer is attempted.
+        contract.distribute(&env, employer.clone(), allocations, token_address);
+    }
+
+    #[test]
+    fn test_distribute_then_execute_payment_pulls_after_unlock() {
+        let env = Env::default();
+        let (employer, worker, customer) = env.accounts().generate(3);
+
+        let contract = FairPaymentContract::deploy(&env);
+        contract.init(&env, employer.clone(), worker.clone(), customer.clone());
+
+        let token_address = Address::from([0u8; 32]);
+        let now = env.ledger().timestamp();
+
+        let allocations = Vec::from_array(
+            &env,
+            [Allocation { recipient: worker.clone(), amount: 300, unlock_timestamp: now + 1000 }],
+        );
+        contract.distribute(&env, employer.clone(), allocations, token_address);
+
+        // Still locked before the unlock timestamp.
+        env.ledger().advance_timestamp(500);
+        assert!(env.storage().instance().get::<_, PendingAllocation>(&DataKey::Allocation(worker.clone())).is_some());
+
+        // Past the unlock timestamp, the worker can now pull it via execute_payment.
+        env.ledger().advance_timestamp(501);
+        contract.execute_payment(&env, worker.clone(), token_address);
+
+        assert!(env.storage().instance().get::<_, PendingAllocation>(&DataKey::Allocation(worker.clone())).is_none());
+    }
 }
 
 fn is_initialized(env: &Env) -> bool {